@@ -18,6 +18,26 @@ pub struct Config {
 pub struct GlobalConfig {
     #[serde(default)]
     pub ignores: Vec<String>,
+
+    /// `None` means the config layer didn't mention this field, so [`Config::merge`] can tell
+    /// that apart from a layer that explicitly chose `false` and avoid clobbering an earlier
+    /// layer's explicit choice.
+    #[serde(default)]
+    pub git_ignore: Option<bool>,
+
+    /// Same "unset vs. explicit" treatment as `git_ignore`, for the same reason.
+    #[serde(default)]
+    pub ignore_files: Option<bool>,
+}
+
+impl GlobalConfig {
+    pub fn git_ignore(&self) -> bool {
+        self.git_ignore.unwrap_or(true)
+    }
+
+    pub fn ignore_files(&self) -> bool {
+        self.ignore_files.unwrap_or(false)
+    }
 }
 
 #[derive(Deserialize, Debug)]
@@ -35,6 +55,28 @@ pub struct FormatterConfig {
     pub env: BTreeMap<String, String>,
 
     pub patterns: Vec<String>,
+
+    #[serde(default)]
+    pub invocation: Invocation,
+
+    /// Caps how many files are passed to a single `Invocation::Batch` call, splitting the rest
+    /// into further calls. Use this to stay under the platform's command-line length limit on
+    /// large trees. `None` (the default) means no limit.
+    #[serde(default)]
+    pub max_files_per_call: Option<usize>,
+}
+
+/// How a formatter's `program` is invoked for the files matched by its `patterns`.
+#[derive(Deserialize, Clone, Copy, Default, Debug, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub enum Invocation {
+    /// Invoke the program once, passing all matched files in a single call (or in several calls
+    /// of at most `max_files_per_call` files each, if that is set).
+    #[default]
+    Batch,
+
+    /// Invoke the program once per matched file.
+    PerFile,
 }
 
 #[derive(Error, Debug)]
@@ -73,4 +115,111 @@ impl Config {
             file.is_file().then_some(file)
         })
     }
+
+    /// Loads a layered config for `dir`: an optional user-level base config is overlaid by every
+    /// project config file found walking from the filesystem root down to `dir`, so that teams
+    /// can share a base set of formatters and let subdirectories specialize without duplicating
+    /// the whole file.
+    pub fn load_layered(dir: &Path) -> Result<Config, LoadError> {
+        let mut config = Config {
+            global: GlobalConfig::default(),
+            formatters: BTreeMap::new(),
+        };
+
+        if let Some(user_config_file) = Self::user_config_file() {
+            if user_config_file.is_file() {
+                config = config.merge(Config::load(&user_config_file)?);
+            }
+        }
+
+        let mut ancestors: Vec<&Path> = dir.ancestors().collect();
+        ancestors.reverse(); // filesystem root first, `dir` last
+        for ancestor in ancestors {
+            if let Some(config_file) = Self::check_dir(ancestor) {
+                config = config.merge(Config::load(&config_file)?);
+            }
+        }
+
+        Ok(config)
+    }
+
+    /// Returns the path to the user-level base config in the platform's config directory
+    /// (e.g. `~/.config/forestry/forestry.toml` on Linux), or `None` if it cannot be determined.
+    fn user_config_file() -> Option<PathBuf> {
+        dirs::config_dir().map(|dir| dir.join("forestry").join(Self::FILE_NAMES[1]))
+    }
+
+    /// Merges `other` into `self`: its formatters override `self`'s by name, its
+    /// `global.ignores` are appended after `self`'s, and its `git_ignore`/`ignore_files` override
+    /// `self`'s only if `other` explicitly sets them, so that a later layer which simply omits a
+    /// field doesn't reset an earlier layer's explicit choice back to the default.
+    pub fn merge(mut self, other: Config) -> Config {
+        self.global.ignores.extend(other.global.ignores);
+        if other.global.git_ignore.is_some() {
+            self.global.git_ignore = other.global.git_ignore;
+        }
+        if other.global.ignore_files.is_some() {
+            self.global.ignore_files = other.global.ignore_files;
+        }
+
+        self.formatters.extend(other.formatters);
+
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config(global: GlobalConfig) -> Config {
+        Config {
+            global,
+            formatters: BTreeMap::new(),
+        }
+    }
+
+    #[test]
+    fn merge_appends_ignores() {
+        let base = config(GlobalConfig {
+            ignores: vec!["a".to_owned()],
+            ..Default::default()
+        });
+        let overlay = config(GlobalConfig {
+            ignores: vec!["b".to_owned()],
+            ..Default::default()
+        });
+
+        let merged = base.merge(overlay);
+        assert_eq!(merged.global.ignores, vec!["a".to_owned(), "b".to_owned()]);
+    }
+
+    #[test]
+    fn merge_keeps_an_earlier_explicit_choice_when_a_later_layer_leaves_it_unset() {
+        let base = config(GlobalConfig {
+            git_ignore: Some(false),
+            ignore_files: Some(true),
+            ..Default::default()
+        });
+        let overlay = config(GlobalConfig::default());
+
+        let merged = base.merge(overlay);
+        assert!(!merged.global.git_ignore());
+        assert!(merged.global.ignore_files());
+    }
+
+    #[test]
+    fn merge_lets_a_later_layer_override_an_explicit_choice() {
+        let base = config(GlobalConfig {
+            git_ignore: Some(false),
+            ..Default::default()
+        });
+        let overlay = config(GlobalConfig {
+            git_ignore: Some(true),
+            ..Default::default()
+        });
+
+        let merged = base.merge(overlay);
+        assert!(merged.global.git_ignore());
+    }
 }
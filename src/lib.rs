@@ -1,5 +1,6 @@
 pub mod config;
 pub mod project;
+pub mod report;
 pub mod runner;
 
 mod util {
@@ -1,4 +1,5 @@
 use std::cmp::min;
+use std::env;
 use std::fmt::Write as _;
 use std::path::PathBuf;
 use std::process::ExitCode;
@@ -8,6 +9,7 @@ use clap::{ArgAction, Args, Parser, ValueEnum};
 use log::{error, trace};
 
 use forestry::project::Project;
+use forestry::report::{GithubReporter, Reporter, TextReporter};
 
 /// 🌳 Keep your project directory trees in shape!
 #[derive(Parser, Debug)]
@@ -15,10 +17,42 @@ use forestry::project::Project;
 struct Cli {
     root_dir: Option<PathBuf>,
 
+    /// After the initial run, watch the project tree and re-run affected formatters on change.
+    #[arg(short, long)]
+    watch: bool,
+
+    /// Set how run results are reported.
+    #[arg(long, value_enum, default_value_t)]
+    reporter: ReporterMode,
+
     #[command(flatten)]
     logger_config: LoggerConfig,
 }
 
+#[derive(ValueEnum, Clone, Debug, Default)]
+enum ReporterMode {
+    #[default]
+    Auto,
+    Text,
+    Github,
+}
+
+impl ReporterMode {
+    fn build(&self) -> Box<dyn Reporter> {
+        let is_github = match self {
+            ReporterMode::Github => true,
+            ReporterMode::Text => false,
+            ReporterMode::Auto => env::var_os("GITHUB_ACTIONS").is_some(),
+        };
+
+        if is_github {
+            Box::<GithubReporter>::default()
+        } else {
+            Box::<TextReporter>::default()
+        }
+    }
+}
+
 #[derive(Args, Debug)]
 struct LoggerConfig {
     /// Increase the logging level with each occurrence.
@@ -90,7 +124,12 @@ fn try_main() -> anyhow::Result<bool> {
     trace!("cli = {:#?}", &cli);
 
     let project = Project::load(cli.root_dir).context("failed to load project")?;
-    let success = project.run();
+    let mut reporter = cli.reporter.build();
+    let success = project.run(reporter.as_mut());
+
+    if cli.watch {
+        project.watch().context("failed to watch project")?;
+    }
 
     Ok(success)
 }
@@ -1,19 +1,24 @@
 use std::env;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc;
+use std::time::Duration;
 
 use globset::Candidate;
-use ignore::overrides::OverrideBuilder;
-use ignore::WalkBuilder;
-use log::{debug, error, trace, warn};
+use ignore::gitignore::{Gitignore, GitignoreBuilder};
+use ignore::{Match, WalkBuilder};
+use log::{debug, error, info, trace, warn};
+use notify::{EventKind, RecursiveMode, Watcher};
 use thiserror::Error;
 
 use crate::config;
-use crate::config::Config;
+use crate::config::{Config, GlobalConfig};
+use crate::report::{Reporter, RunnerReport};
 use crate::runner::Runner;
 
 #[derive(Debug)]
 pub struct Project {
     root_dir: PathBuf,
+    global: GlobalConfig,
     runners: Vec<Runner>,
 }
 
@@ -32,11 +37,17 @@ pub enum LoadError {
     CwdNotAccessible,
 }
 
+#[derive(Error, Debug)]
+pub enum WatchError {
+    #[error("failed to set up the filesystem watcher")]
+    NotifyFailed(#[from] notify::Error),
+}
+
 impl Project {
     pub fn load(root_dir: Option<PathBuf>) -> Result<Project, LoadError> {
-        // load config file
-        let (root_dir, config_file) = Self::find_config(root_dir)?;
-        let config = Config::load(&config_file)?;
+        // find the project root, then load its layered config (user base + ancestor overlays)
+        let (root_dir, _config_file) = Self::find_config(root_dir)?;
+        let config = Config::load_layered(&root_dir)?;
 
         trace!("root dir = {:?}", root_dir);
         trace!("config = {:?}", config);
@@ -50,6 +61,7 @@ impl Project {
 
         Ok(Project {
             root_dir,
+            global: config.global,
             runners: formatters,
         })
     }
@@ -69,21 +81,186 @@ impl Project {
         .ok_or(LoadError::NoConfigFile)
     }
 
-    pub fn run(self) -> bool {
+    pub fn run(&self, reporter: &mut dyn Reporter) -> bool {
         let partitions = self.match_runners();
         let mut all_runners_succeeded = true;
         for (runner, files) in partitions {
             debug!("### {}:\n{:#?}", runner.name(), files);
+            let file_count = files.len();
 
-            let res = runner.run(&self.root_dir, files);
-            if let Err(err) = res {
+            let outcome = runner.run(&self.root_dir, files);
+            if let Err(err) = &outcome.result {
                 all_runners_succeeded = false;
                 warn!("formatter {} failed to run: {}", runner.name(), err);
             }
+
+            reporter.report(RunnerReport {
+                name: runner.name().to_owned(),
+                file_count,
+                success: outcome.result.is_ok(),
+                error: outcome.result.as_ref().err().map(|err| err.to_string()),
+                duration: outcome.duration,
+                output: outcome.output,
+            });
         }
+        reporter.finish();
         all_runners_succeeded
     }
 
+    /// How long to wait for more filesystem events before re-running affected runners.
+    const WATCH_DEBOUNCE: Duration = Duration::from_millis(300);
+
+    /// Watches the project directory tree for changes and re-runs only the runners whose
+    /// patterns match the changed paths, instead of re-walking and re-running everything.
+    ///
+    /// This never returns unless setting up the watcher fails.
+    pub fn watch(&self) -> Result<(), WatchError> {
+        // notify reports paths resolved against the canonicalized watched directory, not
+        // necessarily `self.root_dir` verbatim, so events must be stripped against the same base.
+        let watch_root = self.root_dir.canonicalize().unwrap_or_else(|_| self.root_dir.clone());
+
+        let (tx, rx) = mpsc::channel();
+        let mut watcher = notify::recommended_watcher(move |res: notify::Result<notify::Event>| {
+            if let Ok(event) = res {
+                let _ = tx.send(event);
+            }
+        })?;
+        watcher.watch(&watch_root, RecursiveMode::Recursive)?;
+
+        info!("watching {} for changes...", self.root_dir.display());
+        while let Ok(first_event) = rx.recv() {
+            let mut events = vec![first_event];
+            while let Ok(event) = rx.recv_timeout(Self::WATCH_DEBOUNCE) {
+                events.push(event);
+            }
+
+            let changed_paths = events
+                .into_iter()
+                .filter(|event| {
+                    // `Remove` is deliberately excluded: formatters have nothing to format in a
+                    // deleted file, and running one on a missing path just logs a spurious failure.
+                    matches!(event.kind, EventKind::Create(_) | EventKind::Modify(_))
+                })
+                .flat_map(|event| event.paths)
+                .filter(|path| self.should_watch_path(path, &watch_root));
+
+            self.run_changed(&watch_root, changed_paths);
+        }
+
+        Ok(())
+    }
+
+    /// Builds a [`GitignoreBuilder`] seeded with `/.git/` and `global.ignores`, using gitignore
+    /// semantics throughout (bare pattern = ignore, `!`-prefixed = re-include), for callers to add
+    /// further lines or files to before building.
+    fn ignores_builder(&self) -> GitignoreBuilder {
+        let mut builder = GitignoreBuilder::new(&self.root_dir);
+        builder.add_line(None, "/.git/").unwrap();
+        for pattern in &self.global.ignores {
+            if let Err(error) = builder.add_line(None, pattern) {
+                warn!("ignoring invalid pattern {:?} in `global.ignores`: {}", pattern, error);
+            }
+        }
+        builder
+    }
+
+    /// Returns whether `path` should trigger a re-run while watching, mirroring
+    /// [`Self::match_runners`]'s walk: `global.ignores` (and the mandatory `/.git/` exclude)
+    /// apply project-wide, and -- when enabled -- `.gitignore`/`.ignore` files are honored at
+    /// every directory level between the project root and `path`, plus the user's global
+    /// excludes file and the repo's local `.git/info/exclude`, the same sources
+    /// `WalkBuilder::git_ignore`/`git_global`/`git_exclude`/`ignore` consult for the one-shot walk.
+    fn should_watch_path(&self, path: &Path, watch_root: &Path) -> bool {
+        let Ok(rel_path) = path.strip_prefix(watch_root) else {
+            return false;
+        };
+        let is_dir = path.is_dir();
+
+        let ignores = self.ignores_builder().build().expect("ignore patterns should be valid");
+        if ignores.matched_path_or_any_parents(rel_path, is_dir).is_ignore() {
+            return false;
+        }
+
+        !self.is_ignored_by_nested_ignore_files(path, is_dir, watch_root)
+    }
+
+    /// Checks `.ignore`/`.gitignore` files at every directory level from `path` up to
+    /// `watch_root` (nearer directories take precedence over shallower ones, same as `git`
+    /// itself), then falls back to the user's global excludes file and `.git/info/exclude`.
+    fn is_ignored_by_nested_ignore_files(&self, path: &Path, is_dir: bool, watch_root: &Path) -> bool {
+        let file_names: Vec<&str> = [self.global.ignore_files().then_some(".ignore"), self.global.git_ignore().then_some(".gitignore")]
+            .into_iter()
+            .flatten()
+            .collect();
+        if file_names.is_empty() {
+            return false;
+        }
+
+        let mut dir = path.parent();
+        while let Some(current) = dir {
+            for file_name in &file_names {
+                let ignore_file = current.join(file_name);
+                if ignore_file.is_file() {
+                    let (matcher, _) = Gitignore::new(&ignore_file);
+                    match matcher.matched(path, is_dir) {
+                        Match::Ignore(_) => return true,
+                        Match::Whitelist(_) => return false,
+                        Match::None => {}
+                    }
+                }
+            }
+            if current == watch_root {
+                break;
+            }
+            dir = current.parent();
+        }
+
+        if self.global.git_ignore() {
+            let (global_gitignore, _) = Gitignore::global();
+            if global_gitignore.matched(path, is_dir).is_ignore() {
+                return true;
+            }
+
+            let exclude_file = watch_root.join(".git").join("info").join("exclude");
+            if exclude_file.is_file() {
+                let (exclude, _) = Gitignore::new(&exclude_file);
+                if exclude.matched(path, is_dir).is_ignore() {
+                    return true;
+                }
+            }
+        }
+
+        false
+    }
+
+    /// Partitions the given changed paths by the runner they match,
+    /// then runs each affected runner on just those paths.
+    fn run_changed<I: IntoIterator<Item = PathBuf>>(&self, watch_root: &Path, changed_paths: I) {
+        let mut partitions: Vec<Partition> = (self.runners.iter())
+            .map(|runner| (runner, Vec::new()))
+            .collect();
+
+        for path in changed_paths {
+            let Ok(rel_path) = path.strip_prefix(watch_root) else {
+                continue;
+            };
+            if let Some(files) = Self::match_path(&mut partitions, rel_path) {
+                files.push(path.clone());
+            }
+        }
+
+        for (runner, files) in partitions {
+            if files.is_empty() {
+                continue;
+            }
+            debug!("### {}:\n{:#?}", runner.name(), files);
+
+            if let Err(err) = runner.run(&self.root_dir, files).result {
+                warn!("formatter {} failed to run: {}", runner.name(), err);
+            }
+        }
+    }
+
     /// Walks the whole project directory tree recursively
     /// and partitions it by matching files against the runners' patterns.
     fn match_runners(&self) -> Vec<Partition> {
@@ -92,17 +269,25 @@ impl Project {
             .map(|runner| (runner, Vec::new()))
             .collect();
 
-        let git_dir_override = OverrideBuilder::new(&self.root_dir)
-            .add("!/.git/")
-            .unwrap()
-            .build()
-            .unwrap();
+        // `global.ignores` (and the mandatory `/.git/` exclude) use gitignore semantics, so they're
+        // applied as a `Gitignore` matcher via `filter_entry` rather than `WalkBuilder::overrides`:
+        // `OverrideBuilder` switches into whitelist mode as soon as it sees a non-`!` pattern,
+        // which would silently drop every other file from the walk.
+        let ignores = self.ignores_builder().build().expect("ignore patterns should be valid");
+        let root_dir = self.root_dir.clone();
 
         // walk the project directory tree
         let walk = WalkBuilder::new(&self.root_dir)
-            .overrides(git_dir_override)
-            .ignore(false)
+            .git_ignore(self.global.git_ignore())
+            .git_global(self.global.git_ignore())
+            .git_exclude(self.global.git_ignore())
+            .ignore(self.global.ignore_files())
             .hidden(false)
+            .filter_entry(move |entry| {
+                let rel_path = entry.path().strip_prefix(&root_dir).unwrap_or_else(|_| entry.path());
+                let is_dir = entry.file_type().is_some_and(|file_type| file_type.is_dir());
+                !ignores.matched_path_or_any_parents(rel_path, is_dir).is_ignore()
+            })
             .build();
         for entry in walk {
             let entry = match entry {
@@ -121,30 +306,12 @@ impl Project {
 
             let path = entry.path();
             let rel_path = path.strip_prefix(&self.root_dir).unwrap();
-            let rel_path_candidate = Candidate::new(rel_path);
 
             // match each file against the glob sets of each partition's runner:
             //   if the file matches no glob sets, print a warning
             //   if the file matches exactly one glob set, add it to that partition
             //   if the file matches multiple glob sets, print a warning
-
-            let mut matched_runner_files: Option<&mut Vec<PathBuf>> = None;
-            for (runner, paths) in &mut partitions {
-                let is_match = runner.glob_set().is_match_candidate(&rel_path_candidate);
-                if is_match {
-                    if matched_runner_files.is_none() {
-                        matched_runner_files = Some(paths);
-                    } else {
-                        warn!(
-                            "file {} is already matched by another runner, using only the first",
-                            rel_path.display()
-                        );
-                        break;
-                    }
-                }
-            }
-
-            if let Some(files) = matched_runner_files {
+            if let Some(files) = Self::match_path(&mut partitions, rel_path) {
                 files.push(entry.into_path());
             } else {
                 warn!("file {} is not matched by any runner", rel_path.display());
@@ -153,6 +320,30 @@ impl Project {
 
         partitions
     }
+
+    /// Finds the partition whose runner's glob set matches `rel_path`, warning (and picking the
+    /// first) if more than one runner claims the same file. Returns `None` if no runner matches.
+    fn match_path<'p>(partitions: &'p mut [Partition], rel_path: &Path) -> Option<&'p mut Vec<PathBuf>> {
+        let rel_path_candidate = Candidate::new(rel_path);
+
+        let mut matched_runner_files: Option<&mut Vec<PathBuf>> = None;
+        for (runner, paths) in partitions {
+            let is_match = runner.glob_set().is_match_candidate(&rel_path_candidate);
+            if is_match {
+                if matched_runner_files.is_none() {
+                    matched_runner_files = Some(paths);
+                } else {
+                    warn!(
+                        "file {} is already matched by another runner, using only the first",
+                        rel_path.display()
+                    );
+                    break;
+                }
+            }
+        }
+
+        matched_runner_files
+    }
 }
 
 type Partition<'a> = (&'a Runner, Vec<PathBuf>);
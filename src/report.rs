@@ -0,0 +1,72 @@
+use std::time::Duration;
+
+/// The outcome of running a single runner, ready to be surfaced by a [`Reporter`].
+#[derive(Debug)]
+pub struct RunnerReport {
+    pub name: String,
+    pub file_count: usize,
+    pub success: bool,
+    /// Why the runner failed, if it didn't succeed. `None` whenever `success` is `true`.
+    pub error: Option<String>,
+    pub duration: Duration,
+    pub output: String,
+}
+
+/// Surfaces each runner's [`RunnerReport`] to the user as a project runs.
+pub trait Reporter {
+    /// Called once per runner, right after it finished.
+    fn report(&mut self, report: RunnerReport);
+
+    /// Called once after every runner finished.
+    fn finish(&mut self) {}
+}
+
+/// Prints each runner's captured output as it finishes, then a summary table at the end.
+#[derive(Default)]
+pub struct TextReporter {
+    reports: Vec<RunnerReport>,
+}
+
+impl Reporter for TextReporter {
+    fn report(&mut self, report: RunnerReport) {
+        if !report.output.is_empty() {
+            print!("{}", report.output);
+        }
+        self.reports.push(report);
+    }
+
+    fn finish(&mut self) {
+        println!("{:<20} {:>6} {:>7} {:>10}", "runner", "files", "status", "duration");
+        for report in &self.reports {
+            println!(
+                "{:<20} {:>6} {:>7} {:>10.2?}",
+                report.name,
+                report.file_count,
+                if report.success { "ok" } else { "failed" },
+                report.duration,
+            );
+            if let Some(error) = &report.error {
+                println!("  {}", error);
+            }
+        }
+    }
+}
+
+/// Wraps each runner's output in GitHub Actions workflow commands, so failures show up as
+/// annotations in the Actions UI instead of being buried in the raw log.
+#[derive(Default)]
+pub struct GithubReporter;
+
+impl Reporter for GithubReporter {
+    fn report(&mut self, report: RunnerReport) {
+        println!("::group::{} ({} files)", report.name, report.file_count);
+        if !report.output.is_empty() {
+            print!("{}", report.output);
+        }
+        println!("::endgroup::");
+
+        if let Some(error) = &report.error {
+            println!("::error ::formatter {} failed: {}", report.name, error);
+        }
+    }
+}
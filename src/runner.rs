@@ -1,14 +1,15 @@
 use std::cmp::Ordering;
 use std::collections::HashMap;
 use std::ffi::OsStr;
-use std::path::Path;
-use std::process::Command;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Output, Stdio};
+use std::time::{Duration, Instant};
 
 use globset::{Glob, GlobSet, GlobSetBuilder};
 use log::trace;
 use thiserror::Error;
 
-use crate::config::FormatterConfig;
+use crate::config::{FormatterConfig, Invocation};
 
 #[derive(Debug)]
 pub struct Runner {
@@ -19,6 +20,14 @@ pub struct Runner {
     shell: bool,
     args: Vec<String>,
     env: HashMap<String, String>,
+    invocation: Invocation,
+    max_files_per_call: Option<usize>,
+}
+
+/// The file(s) available for template substitution in a given invocation of a runner.
+enum TemplateFiles<'a> {
+    Batch(&'a [PathBuf]),
+    File(&'a Path),
 }
 
 impl PartialEq<Self> for Runner {
@@ -43,11 +52,33 @@ impl Ord for Runner {
 
 #[derive(Error, Debug)]
 pub enum Error {
-    #[error(transparent)]
-    ExecFailed(std::io::Error),
+    #[error("could not find `{program}` on PATH")]
+    ProgramNotFound { program: String },
+
+    #[error("failed to execute `{program}`: {source}")]
+    ExecFailed {
+        program: String,
+        #[source]
+        source: std::io::Error,
+    },
 
-    #[error("executed program did not exit successfully")]
-    ProgramFailed,
+    #[error("`{program}` exited with code {code}")]
+    ExitedWithCode { program: String, code: i32 },
+
+    #[error("`{program}` was terminated by signal {signal}")]
+    Terminated { program: String, signal: i32 },
+
+    #[error("`{program}` exited with an unknown status")]
+    ExitedUnknown { program: String },
+}
+
+/// The result of a [`Runner::run`] call: whether it succeeded, its combined captured
+/// stdout/stderr (across every invocation, if there was more than one), and how long it took.
+#[derive(Debug)]
+pub struct RunOutcome {
+    pub result: Result<(), Error>,
+    pub output: String,
+    pub duration: Duration,
 }
 
 impl Runner {
@@ -60,6 +91,8 @@ impl Runner {
             shell: fmt.shell,
             args: fmt.args,
             env: fmt.env.into_iter().collect(),
+            invocation: fmt.invocation,
+            max_files_per_call: fmt.max_files_per_call,
         };
         Ok(runner)
     }
@@ -74,13 +107,82 @@ impl Runner {
         builder.build()
     }
 
-    /// Executes the runner once in a specific directory for a set of paths.
-    pub fn run<I, S>(&self, working_dir: &Path, paths: I) -> Result<(), Error>
+    /// Executes the runner in a specific directory for a set of paths, either in a single
+    /// invocation with all paths (`Invocation::Batch`, split into several invocations of at most
+    /// `max_files_per_call` paths each if that is set) or once per path (`Invocation::PerFile`),
+    /// as configured, and captures each invocation's combined stdout/stderr.
+    pub fn run<I, S>(&self, working_dir: &Path, paths: I) -> RunOutcome
     where
         I: IntoIterator<Item = S>,
         S: AsRef<OsStr>,
     {
-        // build command
+        let paths: Vec<PathBuf> = paths.into_iter().map(|path| PathBuf::from(path.as_ref())).collect();
+
+        let started_at = Instant::now();
+        let (result, output) = match self.invocation {
+            Invocation::Batch => self.run_batch(working_dir, &paths),
+            Invocation::PerFile => {
+                let mut output = String::new();
+                let mut first_error = None;
+                for path in &paths {
+                    let (res, out) = self.run_once(working_dir, &TemplateFiles::File(path));
+                    output.push_str(&out);
+                    if let Err(err) = res {
+                        first_error.get_or_insert(err);
+                    }
+                }
+                (first_error.map_or(Ok(()), Err), output)
+            }
+        };
+
+        RunOutcome {
+            result,
+            output,
+            duration: started_at.elapsed(),
+        }
+    }
+
+    fn run_batch(&self, working_dir: &Path, paths: &[PathBuf]) -> (Result<(), Error>, String) {
+        let mut output = String::new();
+        let mut first_error = None;
+        for chunk in self.chunk_paths(paths) {
+            let mut cmd = self.new_cmd(working_dir);
+            let (args, consumed_files) = self.render_args(working_dir, &TemplateFiles::Batch(chunk));
+            cmd.args(args);
+            if !consumed_files {
+                // no placeholder consumed the paths, so fall back to appending them
+                cmd.args(chunk);
+            }
+
+            let (res, out) = self.exec(cmd);
+            output.push_str(&out);
+            if let Err(err) = res {
+                first_error.get_or_insert(err);
+            }
+        }
+
+        (first_error.map_or(Ok(()), Err), output)
+    }
+
+    /// Splits `paths` into batches of at most `max_files_per_call` (if set), so a single
+    /// invocation's command line can't overflow the platform's length limit.
+    fn chunk_paths<'p>(&self, paths: &'p [PathBuf]) -> Vec<&'p [PathBuf]> {
+        if paths.is_empty() {
+            return vec![paths];
+        }
+        match self.max_files_per_call {
+            Some(max) if max > 0 => paths.chunks(max).collect(),
+            _ => vec![paths],
+        }
+    }
+
+    fn run_once(&self, working_dir: &Path, files: &TemplateFiles) -> (Result<(), Error>, String) {
+        let mut cmd = self.new_cmd(working_dir);
+        cmd.args(self.render_args(working_dir, files).0);
+        self.exec(cmd)
+    }
+
+    fn new_cmd(&self, working_dir: &Path) -> Command {
         let mut cmd = if self.shell {
             self.new_shell_cmd()
         } else {
@@ -88,22 +190,126 @@ impl Runner {
         };
         cmd.current_dir(working_dir);
         cmd.envs(&self.env);
-        cmd.args(&self.args);
-        cmd.args(paths);
+        cmd
+    }
+
+    fn exec(&self, mut cmd: Command) -> (Result<(), Error>, String) {
+        cmd.stdout(Stdio::piped());
+        cmd.stderr(Stdio::piped());
 
-        // execute command
         trace!("(runner {}) executing {:?}", self.name, cmd);
-        let status = cmd.status().map_err(Error::ExecFailed)?;
+        let output = match cmd.output() {
+            Ok(output) => output,
+            Err(source) => {
+                let error = if source.kind() == std::io::ErrorKind::NotFound {
+                    Error::ProgramNotFound {
+                        program: self.program.clone(),
+                    }
+                } else {
+                    Error::ExecFailed {
+                        program: self.program.clone(),
+                        source,
+                    }
+                };
+                return (Err(error), String::new());
+            }
+        };
 
-        // inspect command result
-        trace!("(runner {}) status of last command {:?}", self.name, status);
-        if !status.success() {
-            Err(Error::ProgramFailed)
-        } else {
+        trace!("(runner {}) status of last command {:?}", self.name, output.status);
+        let captured = Self::captured_output(&output);
+        let result = if output.status.success() {
             Ok(())
+        } else {
+            Err(Self::exit_error(&self.program, &output.status))
+        };
+        (result, captured)
+    }
+
+    fn exit_error(program: &str, status: &std::process::ExitStatus) -> Error {
+        match status.code() {
+            Some(code) => Error::ExitedWithCode {
+                program: program.to_owned(),
+                code,
+            },
+            None => Self::signal_error(program, status),
+        }
+    }
+
+    #[cfg(unix)]
+    fn signal_error(program: &str, status: &std::process::ExitStatus) -> Error {
+        use std::os::unix::process::ExitStatusExt;
+        match status.signal() {
+            Some(signal) => Error::Terminated {
+                program: program.to_owned(),
+                signal,
+            },
+            None => Error::ExitedUnknown {
+                program: program.to_owned(),
+            },
+        }
+    }
+
+    #[cfg(not(unix))]
+    fn signal_error(program: &str, _status: &std::process::ExitStatus) -> Error {
+        Error::ExitedUnknown {
+            program: program.to_owned(),
         }
     }
 
+    fn captured_output(output: &Output) -> String {
+        let mut text = String::from_utf8_lossy(&output.stdout).into_owned();
+        if !output.stderr.is_empty() {
+            text.push_str(&String::from_utf8_lossy(&output.stderr));
+        }
+        text
+    }
+
+    /// Renders the `{file}`/`{files}`/`{root}`/`{name}` placeholders in `args`, expanding a
+    /// whole-arg `{files}` into one argv entry per path. Also reports whether any arg actually
+    /// consumed a `{files}` token, so callers can tell a real substitution (however it renders)
+    /// apart from an escaped `{{files}}` that merely contains the same substring.
+    fn render_args(&self, root: &Path, files: &TemplateFiles) -> (Vec<String>, bool) {
+        let mut consumed_files = false;
+        let args = self
+            .args
+            .iter()
+            .flat_map(|arg| {
+                let (rendered, arg_consumed_files) = self.render_arg(arg, root, files);
+                consumed_files |= arg_consumed_files;
+                rendered
+            })
+            .collect();
+        (args, consumed_files)
+    }
+
+    fn render_arg(&self, arg: &str, root: &Path, files: &TemplateFiles) -> (Vec<String>, bool) {
+        if let TemplateFiles::Batch(paths) = files {
+            if arg == "{files}" {
+                let rendered = paths.iter().map(|path| path.display().to_string()).collect();
+                return (rendered, true);
+            }
+        }
+
+        let mut consumed_files = false;
+        let rendered = substitute_placeholders(arg, |token| match (token, files) {
+            ("root", _) => Some(root.display().to_string()),
+            ("name", _) => Some(self.name.clone()),
+            ("file", TemplateFiles::File(path)) => Some(path.display().to_string()),
+            ("files", TemplateFiles::Batch(paths)) => {
+                consumed_files = true;
+                Some(
+                    paths
+                        .iter()
+                        .map(|path| path.display().to_string())
+                        .collect::<Vec<_>>()
+                        .join(" "),
+                )
+            }
+            _ => None,
+        });
+        (vec![rendered], consumed_files)
+    }
+
     #[cfg(windows)]
     fn new_shell_cmd(&self) -> Command {
         let mut cmd = Command::new("cmd.exe");
@@ -133,4 +339,120 @@ impl Runner {
     pub fn env(&self) -> &HashMap<String, String> {
         &self.env
     }
+    pub fn invocation(&self) -> Invocation {
+        self.invocation
+    }
+}
+
+/// Replaces `{token}` placeholders in `template` with whatever `resolve` returns for `token`.
+/// An unresolved (i.e. unknown) placeholder is left untouched, including its braces.
+/// `{{` and `}}` are escapes for a literal `{` and `}`.
+fn substitute_placeholders(template: &str, mut resolve: impl FnMut(&str) -> Option<String>) -> String {
+    let mut output = String::with_capacity(template.len());
+    let mut rest = template;
+    while let Some(start) = rest.find(|c: char| c == '{' || c == '}') {
+        output.push_str(&rest[..start]);
+        rest = &rest[start..];
+
+        if let Some(after) = rest.strip_prefix("{{") {
+            output.push('{');
+            rest = after;
+            continue;
+        }
+
+        if rest.starts_with('}') {
+            // `}}` is an escaped `}`; a lone `}` is kept as-is
+            output.push('}');
+            rest = rest.strip_prefix("}}").unwrap_or(&rest[1..]);
+            continue;
+        }
+
+        let Some(end) = rest.find('}') else {
+            // unterminated `{`: keep as-is
+            output.push('{');
+            rest = &rest[1..];
+            continue;
+        };
+
+        let token = &rest[1..end];
+        match resolve(token) {
+            Some(value) => output.push_str(&value),
+            None => output.push_str(&rest[..=end]),
+        }
+        rest = &rest[end + 1..];
+    }
+    output.push_str(rest);
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn substitute_placeholders_resolves_known_tokens() {
+        let rendered = substitute_placeholders("{a}-{b}", |token| match token {
+            "a" => Some("1".to_owned()),
+            "b" => Some("2".to_owned()),
+            _ => None,
+        });
+        assert_eq!(rendered, "1-2");
+    }
+
+    #[test]
+    fn substitute_placeholders_leaves_unknown_tokens_untouched() {
+        let rendered = substitute_placeholders("{known}/{unknown}", |token| match token {
+            "known" => Some("ok".to_owned()),
+            _ => None,
+        });
+        assert_eq!(rendered, "ok/{unknown}");
+    }
+
+    #[test]
+    fn substitute_placeholders_unescapes_doubled_braces_without_resolving() {
+        let rendered = substitute_placeholders("{{files}}", |_| panic!("should not be called"));
+        assert_eq!(rendered, "{files}");
+    }
+
+    #[test]
+    fn render_arg_reports_files_consumed_only_when_the_files_token_is_actually_substituted() {
+        let runner = test_runner(vec!["{{files}}".to_owned()]);
+        let files = TemplateFiles::Batch(&[]);
+
+        let (rendered, consumed_files) = runner.render_arg("{{files}}", Path::new("/root"), &files);
+        assert_eq!(rendered, vec!["{files}".to_owned()]);
+        assert!(!consumed_files, "an escaped `{{{{files}}}}` must not count as a consumed placeholder");
+    }
+
+    #[test]
+    fn chunk_paths_splits_into_groups_of_at_most_max_files_per_call() {
+        let mut runner = test_runner(vec![]);
+        runner.max_files_per_call = Some(2);
+        let paths = vec![PathBuf::from("a"), PathBuf::from("b"), PathBuf::from("c")];
+
+        let chunks = runner.chunk_paths(&paths);
+        assert_eq!(chunks, vec![&paths[0..2], &paths[2..3]]);
+    }
+
+    #[test]
+    fn chunk_paths_keeps_a_single_chunk_when_unset() {
+        let runner = test_runner(vec![]);
+        let paths = vec![PathBuf::from("a"), PathBuf::from("b")];
+
+        let chunks = runner.chunk_paths(&paths);
+        assert_eq!(chunks, vec![&paths[..]]);
+    }
+
+    fn test_runner(args: Vec<String>) -> Runner {
+        Runner {
+            name: "test".to_owned(),
+            glob_set: GlobSetBuilder::new().build().unwrap(),
+            program: "true".to_owned(),
+            shell: false,
+            args,
+            env: HashMap::new(),
+            invocation: Invocation::Batch,
+            max_files_per_call: None,
+        }
+    }
 }